@@ -1,55 +1,252 @@
-use nom::{Err, Needed};
+use nom::{Err, ErrorKind, Needed};
 use std::error;
 use std::fmt;
 
+/// `ErrorKind::Custom` tag `parsers::production` attaches to a failure past
+/// `::=`, marking it as a committed cut point rather than a backtrackable
+/// alternative.
+pub(crate) const FATAL_CUT_CODE: u32 = 1;
+
+fn is_fatal_kind(kind: &ErrorKind) -> bool {
+    match *kind {
+        ErrorKind::Custom(code) => code == FATAL_CUT_CODE,
+        _ => false,
+    }
+}
+
+pub(crate) fn is_fatal(err: &Err<&[u8]>) -> bool {
+    match *err {
+        Err::Position(ref kind, _) | Err::NodePosition(ref kind, _, _) => is_fatal_kind(kind),
+        Err::Code(ref kind) | Err::Node(ref kind, _) => is_fatal_kind(kind),
+    }
+}
+
+/// A 1-indexed line/column position within a grammar's source text, plus
+/// the raw byte offset it corresponds to.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl Location {
+    fn from_offset(input: &[u8], offset: usize) -> Self {
+        let offset = offset.min(input.len());
+        let mut line = 1;
+        let mut column = 1;
+        for &byte in &input[..offset] {
+            if byte == b'\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        Location {
+            line,
+            column,
+            offset,
+        }
+    }
+}
+
+/// The underlying nom error a `ParseError` was converted from. nom's
+/// `Err<&[u8]>` borrows from the input it failed on, so it's captured here
+/// as its `Debug` rendering rather than the borrowed value itself.
+#[derive(PartialEq, Debug, Clone)]
+pub struct NomErrorCause(String);
+
+impl fmt::Display for NomErrorCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "nom parser error: {}", self.0)
+    }
+}
+
+impl error::Error for NomErrorCause {}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Error {
-    ParseError(String),
+    ParseError {
+        message: String,
+        location: Option<Location>,
+        cause: Option<NomErrorCause>,
+        /// `true` once the parser has committed to a production (after
+        /// `::=`) and shouldn't be backtracked out of by `alt`; also `true`
+        /// for trailing input left after the last production.
+        fatal: bool,
+    },
     ParseIncomplete(String),
     GenerateError(String),
     RecursionLimit(String),
+    /// Output exceeded `GenerateConfig::max_tokens` (as opposed to
+    /// `RecursionLimit`, which is about expansion depth).
+    GenerateLimit(String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Error::ParseError(ref s) => write!(f, "{}", s),
+            Error::ParseError {
+                ref message,
+                location: Some(ref loc),
+                ..
+            } => {
+                // `message` may already carry the "Parsing error: " prefix
+                // baked in by `describe`; don't repeat it here.
+                let message = message.trim_start_matches("Parsing error: ");
+                write!(
+                    f,
+                    "parse error at line {}, column {}: {}",
+                    loc.line, loc.column, message
+                )
+            }
+            Error::ParseError {
+                ref message,
+                location: None,
+                ..
+            } => write!(f, "{}", message),
             Error::ParseIncomplete(ref s) => write!(f, "{}", s),
             Error::GenerateError(ref s) => write!(f, "{}", s),
             Error::RecursionLimit(ref s) => write!(f, "{}", s),
+            Error::GenerateLimit(ref s) => write!(f, "{}", s),
         }
     }
 }
 
 impl error::Error for Error {
-    fn description(&self) -> &str {
-        "BNF error"
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::ParseError {
+                cause: Some(ref cause),
+                ..
+            } => Some(cause),
+            _ => None,
+        }
     }
 }
 
-impl<'a> From<Err<&'a [u8]>> for Error {
-    fn from(err: Err<&[u8]>) -> Self {
-        let string = match err {
-            Err::Code(_) => String::from("Parsing error: Unknown origin"),
-            Err::Node(_, n) => n
-                .iter()
-                .fold(String::from("Parsing error: Unknown origin."), |s, e| {
-                    s + &format!(" {}", e)
-                }),
-            Err::Position(_, p) => format!(
-                "Parsing error: When input is {}",
-                String::from_utf8_lossy(p)
-            ),
-            Err::NodePosition(_, p, n) => n.iter().fold(
+/// Builds a message for a nom error, plus the remaining input's length
+/// (needed by callers to resolve a `Location`).
+fn describe(err: &Err<&[u8]>) -> (String, Option<usize>) {
+    match *err {
+        Err::Code(_) => (String::from("Parsing error: Unknown origin"), None),
+        Err::Node(_, ref n) => {
+            // `n` is a single boxed cause (nom 1.x's `Err::Node` wraps one
+            // inner `Err`, not a collection), so describe it directly.
+            let (inner, _) = describe(n);
+            (inner, None)
+        }
+        Err::Position(ref kind, p) => (
+            if is_fatal_kind(kind) {
+                String::from("Parsing error: expected term after \"::=\"")
+            } else {
                 format!(
-                    "Parsing error: When input is {}.",
+                    "Parsing error: When input is {}",
                     String::from_utf8_lossy(p)
-                ),
-                |s, e| s + &format!(" {}", e),
+                )
+            },
+            Some(p.len()),
+        ),
+        Err::NodePosition(ref kind, p, ref n) => (
+            if is_fatal_kind(kind) {
+                String::from("Parsing error: expected term after \"::=\"")
+            } else {
+                let (inner, _) = describe(n);
+                format!(
+                    "Parsing error: When input is {}. {}",
+                    String::from_utf8_lossy(p),
+                    inner
+                )
+            },
+            Some(p.len()),
+        ),
+    }
+}
+
+impl<'a> From<Err<&'a [u8]>> for Error {
+    fn from(err: Err<&[u8]>) -> Self {
+        let fatal = is_fatal(&err);
+        let cause = NomErrorCause(format!("{:?}", err));
+        let (message, _) = describe(&err);
+        let error = Error::ParseError {
+            message,
+            location: None,
+            cause: Some(cause),
+            fatal: false,
+        };
+        if fatal {
+            error.into_fatal()
+        } else {
+            error
+        }
+    }
+}
+
+impl Error {
+    /// Like `Error::from`, but resolves a `Location` using the original,
+    /// full input `Grammar::from_str` still has in hand.
+    pub(crate) fn from_nom_err_with_input(err: Err<&[u8]>, full_input: &[u8]) -> Self {
+        let fatal = is_fatal(&err);
+        let cause = NomErrorCause(format!("{:?}", err));
+        let (message, remaining_len) = describe(&err);
+        let location = remaining_len.map(|remaining| {
+            let offset = full_input.len().saturating_sub(remaining);
+            Location::from_offset(full_input, offset)
+        });
+        let error = Error::ParseError {
+            message,
+            location,
+            cause: Some(cause),
+            fatal: false,
+        };
+        if fatal {
+            error.into_fatal()
+        } else {
+            error
+        }
+    }
+
+    /// A `ParseError` for input left over after `rules_list` stopped
+    /// parsing productions, i.e. unconsumed garbage at the end of a grammar.
+    pub(crate) fn trailing_input(remaining: &[u8], full_input: &[u8]) -> Self {
+        let offset = full_input.len().saturating_sub(remaining.len());
+        let error = Error::ParseError {
+            message: format!(
+                "Parsing error: unexpected trailing input: {}",
+                String::from_utf8_lossy(remaining)
             ),
+            location: Some(Location::from_offset(full_input, offset)),
+            cause: None,
+            fatal: false,
         };
+        error.into_fatal()
+    }
+
+    /// Marks a `ParseError` as fatal; no-op on other variants.
+    pub(crate) fn into_fatal(self) -> Self {
+        match self {
+            Error::ParseError {
+                message,
+                location,
+                cause,
+                ..
+            } => Error::ParseError {
+                message,
+                location,
+                cause,
+                fatal: true,
+            },
+            other => other,
+        }
+    }
 
-        Error::ParseError(string)
+    /// `true` for a `ParseError` marked by `into_fatal`; `false` otherwise.
+    pub fn is_fatal(&self) -> bool {
+        match *self {
+            Error::ParseError { fatal, .. } => fatal,
+            _ => false,
+        }
     }
 }
 
@@ -66,8 +263,8 @@ impl From<Needed> for Error {
 
 #[cfg(test)]
 mod tests {
-    use error::Error;
-    use nom::IResult;
+    use error::{Error, Location, FATAL_CUT_CODE};
+    use nom::{Err, ErrorKind, IResult};
 
     named!(
         give_error_kind,
@@ -92,11 +289,29 @@ mod tests {
         );
 
         match bnf_error.unwrap_err() {
-            Error::ParseError(_) => (),
+            Error::ParseError { .. } => (),
             e => panic!("production error should be error parsing: {:?}", e),
         }
     }
 
+    #[test]
+    fn exposes_nom_error_as_source() {
+        use std::error::Error as StdError;
+
+        let nom_result = give_error_kind("12340".as_bytes());
+        let nom_error;
+        match nom_result {
+            IResult::Error(e) => nom_error = e,
+            _ => panic!("exposes_nom_error_as_source should result in IResult::Error"),
+        }
+
+        let bnf_error = Error::from(nom_error);
+        assert!(
+            bnf_error.source().is_some(),
+            "ParseError should retain the underlying nom error as its source"
+        );
+    }
+
     #[test]
     fn gets_error_incomplete() {
         let nom_result = give_error_kind("".as_bytes());
@@ -137,9 +352,111 @@ mod tests {
         }
     }
 
+    #[test]
+    fn uses_error_generate_limit() {
+        let bnf_error = Error::GenerateLimit(String::from("max_tokens exceeded!"));
+        match bnf_error {
+            Error::GenerateLimit(_) => (),
+            e => panic!("should match on generate limit: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_error_display_with_location() {
+        let bnf_error = Error::ParseError {
+            message: String::from("syntax error!"),
+            location: Some(Location {
+                line: 2,
+                column: 5,
+                offset: 10,
+            }),
+            cause: None,
+            fatal: false,
+        };
+        assert_eq!(
+            bnf_error.to_string(),
+            String::from("parse error at line 2, column 5: syntax error!")
+        );
+    }
+
+    #[test]
+    fn display_with_location_does_not_double_prefix() {
+        let nom_result = give_error_kind("12340".as_bytes());
+        let nom_error = match nom_result {
+            IResult::Error(e) => e,
+            _ => panic!(
+                "display_with_location_does_not_double_prefix should result in IResult::Error"
+            ),
+        };
+
+        let full_input = "12340".as_bytes();
+        let bnf_error = Error::from_nom_err_with_input(nom_error, full_input);
+        let rendered = bnf_error.to_string();
+
+        assert_eq!(
+            rendered.matches("Parsing error:").count(),
+            0,
+            "rendered error should not repeat the \"Parsing error:\" prefix after \"parse error at\": {}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn into_fatal_marks_parse_error_fatal() {
+        let recoverable = Error::ParseError {
+            message: String::from("expected term after ::="),
+            location: None,
+            cause: None,
+            fatal: false,
+        };
+        assert!(!recoverable.is_fatal());
+
+        let fatal = recoverable.into_fatal();
+        assert!(fatal.is_fatal());
+    }
+
+    #[test]
+    fn from_marks_fatal_cut_points_as_fatal() {
+        let cut_point_err: Err<&[u8]> =
+            Err::Position(ErrorKind::Custom(FATAL_CUT_CODE), "".as_bytes());
+        assert!(Error::from(cut_point_err).is_fatal());
+    }
+
+    #[test]
+    fn into_fatal_is_a_no_op_on_other_variants() {
+        let bnf_error = Error::RecursionLimit(String::from("too deep!"));
+        assert!(!bnf_error.clone().into_fatal().is_fatal());
+    }
+
+    #[test]
+    fn location_from_offset_counts_lines_and_columns() {
+        let input = b"abc\ndef\nghi";
+        assert_eq!(
+            Location::from_offset(input, 0),
+            Location {
+                line: 1,
+                column: 1,
+                offset: 0,
+            }
+        );
+        assert_eq!(
+            Location::from_offset(input, 5),
+            Location {
+                line: 2,
+                column: 2,
+                offset: 5,
+            }
+        );
+    }
+
     #[test]
     fn test_error_display() {
-        let parse_error = Error::ParseError(String::from("syntax error!"));
+        let parse_error = Error::ParseError {
+            message: String::from("syntax error!"),
+            location: None,
+            cause: None,
+            fatal: false,
+        };
         let incomplete_error = Error::ParseIncomplete(String::from("incomplete data size!"));
         let generate_error = Error::GenerateError(String::from("error generating!"));
         let recursion_error = Error::RecursionLimit(String::from("recursion limit reached!"));
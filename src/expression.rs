@@ -0,0 +1,33 @@
+use std::fmt;
+use std::slice::Iter;
+
+use term::Term;
+
+/// One alternative right-hand side of a production: an ordered sequence of
+/// terms that must all match (or all be emitted, when generating).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Expression {
+    terms: Vec<Term>,
+}
+
+impl Expression {
+    pub fn new(terms: Vec<Term>) -> Self {
+        Expression { terms }
+    }
+
+    pub fn terms_iter(&self) -> Iter<Term> {
+        self.terms.iter()
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rendered = self
+            .terms
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{}", rendered)
+    }
+}
@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use nom::IResult;
+use rand::Rng;
+
+use error::Error;
+use parsers;
+use production::Production;
+use term::Term;
+
+/// Limits that bound a single `generate`/`generate_with_config` call: how
+/// deep nonterminal expansion may recurse, and how many terminals the
+/// output may contain in total.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerateConfig {
+    pub max_depth: usize,
+    pub max_tokens: usize,
+}
+
+impl Default for GenerateConfig {
+    fn default() -> Self {
+        GenerateConfig {
+            max_depth: 25,
+            max_tokens: 1_000,
+        }
+    }
+}
+
+/// A parsed BNF grammar: an ordered list of productions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grammar {
+    pub productions: Vec<Production>,
+}
+
+impl Grammar {
+    pub fn new(productions: Vec<Production>) -> Self {
+        Grammar { productions }
+    }
+
+    /// Generates a random string from this grammar's first production,
+    /// using `GenerateConfig::default()`.
+    pub fn generate(&self) -> Result<String, Error> {
+        self.generate_with_config(&GenerateConfig::default())
+    }
+
+    /// Like `generate`, but bounded by `config`: nonterminal expansion
+    /// stops at `max_depth` (`Error::RecursionLimit`) and the output stops
+    /// at `max_tokens` emitted terminals (`Error::GenerateLimit`).
+    pub fn generate_with_config(&self, config: &GenerateConfig) -> Result<String, Error> {
+        let start = self.productions.first().ok_or_else(|| {
+            Error::GenerateError(String::from("Could not find a starting production"))
+        })?;
+        let mut tokens_emitted = 0;
+        self.traverse(&start.lhs, config, 0, &mut tokens_emitted)
+    }
+
+    fn traverse(
+        &self,
+        term: &Term,
+        config: &GenerateConfig,
+        depth: usize,
+        tokens_emitted: &mut usize,
+    ) -> Result<String, Error> {
+        match *term {
+            Term::Terminal(ref s) => {
+                *tokens_emitted += 1;
+                if *tokens_emitted > config.max_tokens {
+                    return Err(Error::GenerateLimit(format!(
+                        "generated output exceeded max_tokens ({})",
+                        config.max_tokens
+                    )));
+                }
+                Ok(s.clone())
+            }
+            Term::Nonterminal(ref name) => {
+                if depth >= config.max_depth {
+                    return Err(Error::RecursionLimit(name.clone()));
+                }
+                let production = self.production_for(name).ok_or_else(|| {
+                    Error::GenerateError(format!("Missing production for <{}>", name))
+                })?;
+                let expression = rand::thread_rng()
+                    .choose(&production.rhs)
+                    .ok_or_else(|| Error::GenerateError(format!("<{}> has no expansions", name)))?;
+                let mut rendered = String::new();
+                for inner_term in expression.terms_iter() {
+                    rendered
+                        .push_str(&self.traverse(inner_term, config, depth + 1, tokens_emitted)?);
+                }
+                Ok(rendered)
+            }
+        }
+    }
+
+    fn production_for(&self, name: &str) -> Option<&Production> {
+        self.productions.iter().find(|p| match p.lhs {
+            Term::Nonterminal(ref lhs_name) => lhs_name == name,
+            Term::Terminal(_) => false,
+        })
+    }
+}
+
+impl FromStr for Grammar {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.as_bytes();
+        match parsers::rules_list(input) {
+            IResult::Done(remaining, productions) => {
+                let trailing = parsers::skip_ws(remaining);
+                if trailing.is_empty() {
+                    Ok(Grammar::new(productions))
+                } else {
+                    Err(Error::trailing_input(trailing, input))
+                }
+            }
+            IResult::Error(e) => Err(Error::from_nom_err_with_input(e, input)),
+            IResult::Incomplete(needed) => Err(Error::from(needed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expression::Expression;
+
+    #[test]
+    fn from_str_parses_a_minimal_grammar() {
+        let grammar = "<a> ::= \"hi\"".parse::<Grammar>().unwrap();
+        assert_eq!(grammar.productions.len(), 1);
+    }
+
+    #[test]
+    fn from_str_rejects_trailing_garbage() {
+        match "<a> ::= \"hi\"\ngarbage".parse::<Grammar>() {
+            Err(_) => (),
+            Ok(g) => panic!("expected trailing garbage to be rejected, got {:?}", g),
+        }
+    }
+
+    #[test]
+    fn generate_expands_a_terminating_grammar() {
+        let production = Production::new(
+            Term::Nonterminal(String::from("a")),
+            vec![Expression::new(vec![Term::Terminal(String::from("hi"))])],
+        );
+        let grammar = Grammar::new(vec![production]);
+        assert_eq!(grammar.generate().unwrap(), String::from("hi"));
+    }
+
+    #[test]
+    fn generate_with_config_hits_recursion_limit() {
+        // <a> ::= <a>
+        let production = Production::new(
+            Term::Nonterminal(String::from("a")),
+            vec![Expression::new(vec![Term::Nonterminal(String::from("a"))])],
+        );
+        let grammar = Grammar::new(vec![production]);
+        let config = GenerateConfig {
+            max_depth: 3,
+            max_tokens: 1_000,
+        };
+        match grammar.generate_with_config(&config) {
+            Err(Error::RecursionLimit(ref name)) => assert_eq!(name, "a"),
+            other => panic!("expected RecursionLimit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_str_reports_a_fatal_error_after_cut() {
+        match "<a> ::= nope".parse::<Grammar>() {
+            Err(e) => assert!(e.is_fatal(), "expected a fatal error, got {:?}", e),
+            Ok(g) => panic!("expected a parse error, got {:?}", g),
+        }
+    }
+
+    #[test]
+    fn generate_with_config_hits_generate_limit() {
+        // <a> ::= "x" <a>
+        let production = Production::new(
+            Term::Nonterminal(String::from("a")),
+            vec![Expression::new(vec![
+                Term::Terminal(String::from("x")),
+                Term::Nonterminal(String::from("a")),
+            ])],
+        );
+        let grammar = Grammar::new(vec![production]);
+        let config = GenerateConfig {
+            max_depth: 1_000,
+            max_tokens: 3,
+        };
+        match grammar.generate_with_config(&config) {
+            Err(Error::GenerateLimit(_)) => (),
+            other => panic!("expected GenerateLimit, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! A library for parsing Backus-Naur Form grammars, matching against them,
+//! and generating strings from them.
+
+#[macro_use]
+extern crate nom;
+extern crate rand;
+
+pub mod error;
+pub mod expression;
+pub mod grammar;
+mod parsers;
+pub mod production;
+pub mod term;
+
+pub use error::Error;
+pub use expression::Expression;
+pub use grammar::{GenerateConfig, Grammar};
+pub use production::Production;
+pub use term::Term;
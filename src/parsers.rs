@@ -0,0 +1,134 @@
+use nom::{Err, ErrorKind, IResult};
+
+use error::{is_fatal, FATAL_CUT_CODE};
+use expression::Expression;
+use production::Production;
+use term::Term;
+
+named!(pub nonterminal<Term>,
+    map!(
+        delimited!(tag!("<"), take_until!(">"), tag!(">")),
+        |s: &[u8]| Term::Nonterminal(String::from_utf8_lossy(s).into_owned())
+    )
+);
+
+named!(pub terminal<Term>,
+    map!(
+        delimited!(tag!("\""), take_until!("\""), tag!("\"")),
+        |s: &[u8]| Term::Terminal(String::from_utf8_lossy(s).into_owned())
+    )
+);
+
+named!(pub term<Term>, alt!(nonterminal | terminal));
+
+// nom 1.x has no `ws!` macro (it's a 2.x+ addition, and 2.x+ drop the
+// `Err`/`IResult` shapes the rest of this crate is written against), so
+// whitespace is skipped by hand at each call site instead.
+pub(crate) fn skip_ws(input: &[u8]) -> &[u8] {
+    let mut i = 0;
+    while i < input.len() && (input[i] as char).is_whitespace() {
+        i += 1;
+    }
+    &input[i..]
+}
+
+pub fn expression(input: &[u8]) -> IResult<&[u8], Expression> {
+    let mut terms = Vec::new();
+    let mut remaining = input;
+    loop {
+        // End of input ends the expression; nom would report it as Incomplete.
+        if !terms.is_empty() && skip_ws(remaining).is_empty() {
+            break;
+        }
+        match term(skip_ws(remaining)) {
+            IResult::Done(rest, t) => {
+                terms.push(t);
+                remaining = rest;
+            }
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+            IResult::Error(e) => {
+                if terms.is_empty() {
+                    return IResult::Error(e);
+                }
+                break;
+            }
+        }
+    }
+    IResult::Done(remaining, Expression::new(terms))
+}
+
+pub fn expression_list(input: &[u8]) -> IResult<&[u8], Vec<Expression> > {
+    let (mut remaining, first) = match expression(input) {
+        IResult::Done(rest, e) => (rest, e),
+        IResult::Error(e) => return IResult::Error(e),
+        IResult::Incomplete(n) => return IResult::Incomplete(n),
+    };
+    let mut expressions = vec![first];
+    loop {
+        let after_pipe = match tag!(skip_ws(remaining), "|") {
+            IResult::Done(rest, _) => rest,
+            IResult::Incomplete(_) | IResult::Error(_) => break,
+        };
+        match expression(after_pipe) {
+            IResult::Done(rest, e) => {
+                expressions.push(e);
+                remaining = rest;
+            }
+            IResult::Error(e) => return IResult::Error(e),
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+        }
+    }
+    IResult::Done(remaining, expressions)
+}
+
+// Hand-written rather than `do_parse!` so that a failure past `::=` can be
+// re-tagged as a fatal cut point: once a production has committed to a
+// `lhs ::=`, a malformed rhs is a real syntax error, not a signal to
+// backtrack and try something else.
+pub fn production(input: &[u8]) -> IResult<&[u8], Production> {
+    let (after_lhs, lhs) = match nonterminal(skip_ws(input)) {
+        IResult::Done(rest, lhs) => (rest, lhs),
+        IResult::Error(e) => return IResult::Error(e),
+        IResult::Incomplete(n) => return IResult::Incomplete(n),
+    };
+    let after_assign = match tag!(skip_ws(after_lhs), "::=") {
+        IResult::Done(rest, _) => rest,
+        IResult::Error(e) => return IResult::Error(e),
+        IResult::Incomplete(n) => return IResult::Incomplete(n),
+    };
+    match expression_list(after_assign) {
+        IResult::Done(rest, rhs) => IResult::Done(rest, Production::new(lhs, rhs)),
+        IResult::Incomplete(n) => IResult::Incomplete(n),
+        IResult::Error(_) => {
+            IResult::Error(Err::Position(ErrorKind::Custom(FATAL_CUT_CODE), after_assign))
+        }
+    }
+}
+
+// Hand-written rather than `many1!` so a fatal error from `production` is
+// forwarded as-is instead of risking `many1!` reshaping it into a plain
+// "stop repeating" signal and losing the `FATAL_CUT_CODE` tag.
+pub fn rules_list(input: &[u8]) -> IResult<&[u8], Vec<Production>> {
+    let mut productions = Vec::new();
+    let mut remaining = input;
+    loop {
+        // End of input ends the list; nom would report it as Incomplete.
+        if !productions.is_empty() && skip_ws(remaining).is_empty() {
+            break;
+        }
+        match production(remaining) {
+            IResult::Done(rest, p) => {
+                productions.push(p);
+                remaining = rest;
+            }
+            IResult::Incomplete(n) => return IResult::Incomplete(n),
+            IResult::Error(e) => {
+                if is_fatal(&e) || productions.is_empty() {
+                    return IResult::Error(e);
+                }
+                break;
+            }
+        }
+    }
+    IResult::Done(remaining, productions)
+}
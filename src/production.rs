@@ -0,0 +1,29 @@
+use std::fmt;
+
+use expression::Expression;
+use term::Term;
+
+/// A single grammar rule: `lhs ::= rhs | rhs | ...`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Production {
+    pub lhs: Term,
+    pub rhs: Vec<Expression>,
+}
+
+impl Production {
+    pub fn new(lhs: Term, rhs: Vec<Expression>) -> Self {
+        Production { lhs, rhs }
+    }
+}
+
+impl fmt::Display for Production {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rhs = self
+            .rhs
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        write!(f, "{} ::= {}", self.lhs, rhs)
+    }
+}
@@ -0,0 +1,18 @@
+use std::fmt;
+
+/// A single symbol on the right-hand side of a production: either a literal
+/// terminal or a nonterminal that expands via another production.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Term {
+    Terminal(String),
+    Nonterminal(String),
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Term::Terminal(ref s) => write!(f, "\"{}\"", s),
+            Term::Nonterminal(ref s) => write!(f, "<{}>", s),
+        }
+    }
+}